@@ -0,0 +1,132 @@
+/* elf_abi.rs derives a canonical <arch>_<abi> token from an ELF header
+ * Copyright (C) 2019  Bruno Mondelo Giaramita
+
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+const EF_MIPS_ABI :u32 = 0x0000f000;
+/* ELF flags MIPS ABI mask */
+
+const EF_MIPS_ABI_O32 :u32 = 0x00001000;
+/* ELF flags MIPS o32 ABI */
+
+const EF_MIPS_ABI_O64 :u32 = 0x00002000;
+/* ELF flags MIPS o64 ABI */
+
+const EF_MIPS_ABI_EABI32 :u32 = 0x00003000;
+/* ELF flags MIPS eabi32 ABI */
+
+const EF_MIPS_ABI_EABI64 :u32 = 0x00004000;
+/* ELF flags MIPS eabi64 ABI */
+
+const EF_MIPS_ABI2 :u32 = 0x00000020;
+/* ELF flags MIPS n32 ABI marker */
+
+const EF_ARM_NEW_ABI :u32 = 0x00000080;
+/* ELF flags ARM new ABI */
+
+const EF_ARM_OLD_ABI :u32 = 0x00000100;
+/* ELF flags ARM old ABI */
+
+fn elf_arch(e_machine :u16) -> &'static str {
+    match e_machine {
+        EM_SPARC => "sparc",
+        EM_386 => "x86",
+        EM_MIPS => "mips",
+        EM_PPC => "ppc",
+        EM_PPC64 => "ppc",
+        EM_ARM => "arm",
+        EM_IA_64 => "ia64",
+        EM_X86_64 => "x86",
+        EM_AARCH64 => "arm",
+        EM_RISCV => "riscv",
+        _ => "unknown",
+    }
+}
+/* Derives the base architecture name from e_machine */
+
+fn elf_endian(ei_data :u8) -> String {
+    match ei_data {
+        EI_DATA_LITTLE => String::from("little"),
+        EI_DATA_BIG => String::from("big"),
+        _ => String::from("unknown"),
+    }
+}
+/* Derives the endianness name from ei_data */
+
+fn elf_abi_parts(e_machine :u16, e_flags :u32, ei_class :u8, ei_data :u8)
+    -> (String, String, String) {
+    let word = if ei_class == EI_CLASS_64 { "64" } else { "32" };
+
+    let (arch, abi) = match e_machine {
+        EM_X86_64 => {
+            if ei_class == EI_CLASS_32 {
+                (String::from("x86"), String::from("x32"))
+            }
+            else {
+                (String::from("x86"), String::from("64"))
+            }
+        },
+        EM_386 => (String::from("x86"), String::from("32")),
+        EM_MIPS => {
+            let abi = match e_flags & EF_MIPS_ABI {
+                EF_MIPS_ABI_O32 => String::from("o32"),
+                EF_MIPS_ABI_O64 => String::from("o64"),
+                EF_MIPS_ABI_EABI32 => String::from("eabi32"),
+                EF_MIPS_ABI_EABI64 => String::from("eabi64"),
+                _ => {
+                    if e_flags & EF_MIPS_ABI2 != 0 {
+                        String::from("n32")
+                    }
+                    else if ei_class == EI_CLASS_64 {
+                        String::from("n64")
+                    }
+                    else {
+                        String::from(word)
+                    }
+                },
+            };
+            (String::from("mips"), abi)
+        },
+        EM_ARM => {
+            /* The EABI version (top nibble of e_flags) and the new and old
+               ABI bits are read but do not change the token: ARM always
+               yields the word size */
+            let _eabi = (e_flags & 0xf0000000) >> 28;
+            let _new = e_flags & EF_ARM_NEW_ABI != 0;
+            let _old = e_flags & EF_ARM_OLD_ABI != 0;
+            (String::from("arm"), String::from(word))
+        },
+        _ => (String::from(elf_arch(e_machine)), String::from(word)),
+    };
+
+    (arch, abi, elf_endian(ei_data))
+}
+/* Classifies the header fields into (arch, abi, endian) */
+
+fn elf_abi(header :&ELFHeader32) -> String {
+    let (arch, abi, _) = elf_abi_parts(header.e_machine, header.e_flags,
+        header.e_ident.ei_class, header.e_ident.ei_data);
+
+    format!("{}_{}", arch, abi)
+}
+/* Produces the canonical <arch>_<abi> token for a 32-bit header */
+
+fn elf_abi_64(header :&ELFHeader64) -> String {
+    let (arch, abi, _) = elf_abi_parts(header.e_machine, header.e_flags,
+        header.e_ident.ei_class, header.e_ident.ei_data);
+
+    format!("{}_{}", arch, abi)
+}
+/* Produces the canonical <arch>_<abi> token for a 64-bit header */
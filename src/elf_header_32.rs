@@ -15,6 +15,51 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+const ET_NONE :u16 = 0x00;
+/* ELF header no file type */
+
+const ET_REL :u16 = 0x01;
+/* ELF header relocatable file */
+
+const ET_EXEC :u16 = 0x02;
+/* ELF header executable file */
+
+const ET_DYN :u16 = 0x03;
+/* ELF header shared object file */
+
+const ET_CORE :u16 = 0x04;
+/* ELF header core file */
+
+const EM_SPARC :u16 = 0x02;
+/* ELF header SPARC architecture */
+
+const EM_386 :u16 = 0x03;
+/* ELF header x86 architecture */
+
+const EM_MIPS :u16 = 0x08;
+/* ELF header MIPS architecture */
+
+const EM_PPC :u16 = 0x14;
+/* ELF header PowerPC architecture */
+
+const EM_PPC64 :u16 = 0x15;
+/* ELF header PowerPC 64-bit architecture */
+
+const EM_ARM :u16 = 0x28;
+/* ELF header ARM architecture */
+
+const EM_IA_64 :u16 = 0x32;
+/* ELF header IA-64 architecture */
+
+const EM_X86_64 :u16 = 0x3e;
+/* ELF header x86-64 architecture */
+
+const EM_AARCH64 :u16 = 0xb7;
+/* ELF header AArch64 architecture */
+
+const EM_RISCV :u16 = 0xf3;
+/* ELF header RISC-V architecture */
+
 struct ELFHeader32 {
     e_ident :ELFIdent,        /* 0x00: ident */
     e_type :u16,              /* 0x10: specifies the object file type */
@@ -104,41 +149,209 @@ fn parse_elf_header(buf :&[u8; 54]) -> ELFHeader32 {
 }
 /* Parses a 54-byte buffer into a 32-bit ELFHeader */
 
-fn read_elf_header(file :&mut File) -> Option<ELFHeader32> {
-    let ident = match read_elf_ident(file) {
-        None => return None,
-        Some(i) => i,
-    };
+struct ELFHeader64 {
+    e_ident :ELFIdent,        /* 0x00: ident */
+    e_type :u16,              /* 0x10: specifies the object file type */
+    e_machine :u16,           /* 0x12: specifies the target set architecture */
+    e_version :u32,           /* 0x14: flag set for the original ELF version */
+    e_entry :u64,             /* 0x18: memory address of the entry point */
+    e_phoff :u64,             /* 0x20: points to the program header table */
+    e_shoff :u64,             /* 0x28: points to the start of the section header
+                                 table */
+    e_flags :u32,             /* 0x30: architecture dependant flags */
+    e_shsize :u16,            /* 0x34: size of this header */
+    e_phentsize :u16,         /* 0x36: size of the program header table */
+    e_phnum :u16,             /* 0x38: entries in the program header table */
+    e_shentsize :u16,         /* 0x3a: size of a section header table entry */
+    e_shnum :u16,             /* 0x3c: entries in the section header table */
+    e_shstrndx :u16           /* 0x3e: index of the section header table
+                                 containing section names */
+}
+/* Structure to define the 64-bit ELF header */
+
+enum ELFHeader {
+    Elf32(ELFHeader32),
+    Elf64(ELFHeader64)
+}
+/* Generic ELF header dispatching on ei_class */
+
+fn parse_elf_header_64(buf :&[u8; 64]) -> ELFHeader64 {
+    let mut ident: [u8; 16] = Default::default();
+    ident.copy_from_slice(&buf[0x00..0x10]);
+    let e_ident = parse_elf_ident(&ident);
+
+    let mut e_type :u16 = Default::default();
+    let mut e_machine :u16 = Default::default();
+    let mut e_version :u32 = Default::default();
+    let mut e_entry :u64 = Default::default();
+    let mut e_phoff :u64 = Default::default();
+    let mut e_shoff :u64 = Default::default();
+    let mut e_flags :u32 = Default::default();
+    let mut e_shsize :u16 = Default::default();
+    let mut e_phentsize :u16 = Default::default();
+    let mut e_phnum :u16 = Default::default();
+    let mut e_shentsize :u16 = Default::default();
+    let mut e_shnum :u16 = Default::default();
+    let mut e_shstrndx :u16 = Default::default();
 
-    if ident.ei_class != EI_CLASS_32 {
-        return None;
+    if e_ident.ei_data == EI_DATA_LITTLE {
+        e_type = LittleEndian::read_u16(&buf[0x10..0x12]);
+        e_machine = LittleEndian::read_u16(&buf[0x12..0x14]);
+        e_version = LittleEndian::read_u32(&buf[0x14..0x18]);
+        e_entry = LittleEndian::read_u64(&buf[0x18..0x20]);
+        e_phoff = LittleEndian::read_u64(&buf[0x20..0x28]);
+        e_shoff = LittleEndian::read_u64(&buf[0x28..0x30]);
+        e_flags = LittleEndian::read_u32(&buf[0x30..0x34]);
+        e_shsize = LittleEndian::read_u16(&buf[0x34..0x36]);
+        e_phentsize = LittleEndian::read_u16(&buf[0x36..0x38]);
+        e_phnum = LittleEndian::read_u16(&buf[0x38..0x3a]);
+        e_shentsize = LittleEndian::read_u16(&buf[0x3a..0x3c]);
+        e_shnum = LittleEndian::read_u16(&buf[0x3c..0x3e]);
+        e_shstrndx = LittleEndian::read_u16(&buf[0x3e..0x40]);
+    }
+    else if e_ident.ei_data == EI_DATA_BIG {
+        e_type = BigEndian::read_u16(&buf[0x10..0x12]);
+        e_machine = BigEndian::read_u16(&buf[0x12..0x14]);
+        e_version = BigEndian::read_u32(&buf[0x14..0x18]);
+        e_entry = BigEndian::read_u64(&buf[0x18..0x20]);
+        e_phoff = BigEndian::read_u64(&buf[0x20..0x28]);
+        e_shoff = BigEndian::read_u64(&buf[0x28..0x30]);
+        e_flags = BigEndian::read_u32(&buf[0x30..0x34]);
+        e_shsize = BigEndian::read_u16(&buf[0x34..0x36]);
+        e_phentsize = BigEndian::read_u16(&buf[0x36..0x38]);
+        e_phnum = BigEndian::read_u16(&buf[0x38..0x3a]);
+        e_shentsize = BigEndian::read_u16(&buf[0x3a..0x3c]);
+        e_shnum = BigEndian::read_u16(&buf[0x3c..0x3e]);
+        e_shstrndx = BigEndian::read_u16(&buf[0x3e..0x40]);
     }
 
-    let mut buf = [0u8; 54];
+    ELFHeader64 {
+        e_ident: e_ident,
+        e_type: e_type,
+        e_machine: e_machine,
+        e_version: e_version,
+        e_entry: e_entry,
+        e_phoff: e_phoff,
+        e_shoff: e_shoff,
+        e_flags: e_flags,
+        e_shsize: e_shsize,
+        e_phentsize: e_phentsize,
+        e_phnum: e_phnum,
+        e_shentsize: e_shentsize,
+        e_shnum: e_shnum,
+        e_shstrndx: e_shstrndx
+    }
+}
+/* Parses a 64-byte buffer into a 64-bit ELFHeader */
+
+fn read_header_buf(file :&mut File, buf :&mut [u8]) -> Result<(), ElfError> {
+    file.seek(SeekFrom::Start(0))?;
 
-    if let Err(_) = file.seek(SeekFrom::Start(0)) {
-        return None;
+    let mut got = 0;
+    while got < buf.len() {
+        match file.read(&mut buf[got..])? {
+            0 => break,
+            n => got += n,
+        }
     }
 
-    if let Err(_) = file.read_exact(&mut buf) {
-        return None;
+    if got < buf.len() {
+        return Err(ElfError::Truncated { expected: buf.len(), got });
     }
 
-    let header = parse_elf_header(&buf);
+    Ok(())
+}
+/* Fills a header buffer from the start of file reporting short reads */
+
+fn read_elf_header(file :&mut File) -> Result<ELFHeader, ElfError> {
+    let ident = read_elf_ident(file)?;
+
+    if ident.ei_class == EI_CLASS_32 {
+        let mut buf = [0u8; 54];
+        read_header_buf(file, &mut buf)?;
+
+        Ok(ELFHeader::Elf32(parse_elf_header(&buf)))
+    }
+    else {
+        let mut buf = [0u8; 64];
+        read_header_buf(file, &mut buf)?;
 
-    Some(header)
+        Ok(ELFHeader::Elf64(parse_elf_header_64(&buf)))
+    }
 }
 /* Reads the ELF header from file */
 
+fn write_header_u16(file :&mut File, offset :u64, value :u16, ei_data :u8)
+    -> bool {
+    let mut buf = [0u8; 2];
+
+    if ei_data == EI_DATA_BIG {
+        BigEndian::write_u16(&mut buf, value);
+    }
+    else {
+        LittleEndian::write_u16(&mut buf, value);
+    }
+
+    if let Err(_) = file.seek(SeekFrom::Start(offset)) {
+        return false;
+    }
+
+    if let Err(_) = file.write_all(&buf) {
+        return false;
+    }
+
+    true
+}
+/* Patches a 16-bit header field respecting endianness */
+
+fn set_e_type(file :&mut File, header :&ELFHeader, e_type :u16) -> bool {
+    let ei_data = match header {
+        ELFHeader::Elf32(h) => h.e_ident.ei_data,
+        ELFHeader::Elf64(h) => h.e_ident.ei_data,
+    };
+
+    write_header_u16(file, 0x10, e_type, ei_data)
+}
+/* Rewrites the e_type field (e.g. EXEC <-> DYN) in place */
+
+fn set_e_machine(file :&mut File, header :&ELFHeader, e_machine :u16) -> bool {
+    let ei_data = match header {
+        ELFHeader::Elf32(h) => h.e_ident.ei_data,
+        ELFHeader::Elf64(h) => h.e_ident.ei_data,
+    };
+
+    write_header_u16(file, 0x12, e_machine, ei_data)
+}
+/* Rewrites the e_machine field in place */
+
 impl fmt::Display for ELFHeader32 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let _ = write!(f, "e_ident: {{{}}}; ", self.e_ident);
 
-        let _ = write!(f, "e_type: 0x{:02x}; ", self.e_type);
-        /* Add match */
+        let _ = write!(f, "e_type: 0x{:02x} ", self.e_type);
+        let _ = match self.e_type {
+            ET_NONE => write!(f, "ET_NONE; "),
+            ET_REL => write!(f, "ET_REL; "),
+            ET_EXEC => write!(f, "ET_EXEC; "),
+            ET_DYN => write!(f, "ET_DYN; "),
+            ET_CORE => write!(f, "ET_CORE; "),
+            _ => write!(f, "unknown; "),
+        };
 
-        let _ = write!(f, "e_machine: 0x{:02x}; ", self.e_machine);
-        /* Add match */
+        let _ = write!(f, "e_machine: 0x{:02x} ", self.e_machine);
+        let _ = match self.e_machine {
+            EM_SPARC => write!(f, "EM_SPARC; "),
+            EM_386 => write!(f, "EM_386; "),
+            EM_MIPS => write!(f, "EM_MIPS; "),
+            EM_PPC => write!(f, "EM_PPC; "),
+            EM_PPC64 => write!(f, "EM_PPC64; "),
+            EM_ARM => write!(f, "EM_ARM; "),
+            EM_IA_64 => write!(f, "EM_IA_64; "),
+            EM_X86_64 => write!(f, "EM_X86_64; "),
+            EM_AARCH64 => write!(f, "EM_AARCH64; "),
+            EM_RISCV => write!(f, "EM_RISCV; "),
+            _ => write!(f, "unknown; "),
+        };
 
         let _ = write!(f, "e_version: 0x{:02x}; ", self.e_version);
 
@@ -164,3 +377,67 @@ impl fmt::Display for ELFHeader32 {
     }
 }
 /* Implementation of format to show 32-bit header */
+
+impl fmt::Display for ELFHeader64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let _ = write!(f, "e_ident: {{{}}}; ", self.e_ident);
+
+        let _ = write!(f, "e_type: 0x{:02x} ", self.e_type);
+        let _ = match self.e_type {
+            ET_NONE => write!(f, "ET_NONE; "),
+            ET_REL => write!(f, "ET_REL; "),
+            ET_EXEC => write!(f, "ET_EXEC; "),
+            ET_DYN => write!(f, "ET_DYN; "),
+            ET_CORE => write!(f, "ET_CORE; "),
+            _ => write!(f, "unknown; "),
+        };
+
+        let _ = write!(f, "e_machine: 0x{:02x} ", self.e_machine);
+        let _ = match self.e_machine {
+            EM_SPARC => write!(f, "EM_SPARC; "),
+            EM_386 => write!(f, "EM_386; "),
+            EM_MIPS => write!(f, "EM_MIPS; "),
+            EM_PPC => write!(f, "EM_PPC; "),
+            EM_PPC64 => write!(f, "EM_PPC64; "),
+            EM_ARM => write!(f, "EM_ARM; "),
+            EM_IA_64 => write!(f, "EM_IA_64; "),
+            EM_X86_64 => write!(f, "EM_X86_64; "),
+            EM_AARCH64 => write!(f, "EM_AARCH64; "),
+            EM_RISCV => write!(f, "EM_RISCV; "),
+            _ => write!(f, "unknown; "),
+        };
+
+        let _ = write!(f, "e_version: 0x{:02x}; ", self.e_version);
+
+        let _ = write!(f, "e_entry: 0x{:02x}; ", self.e_entry);
+
+        let _ = write!(f, "e_phoff: 0x{:02x}; ", self.e_phoff);
+
+        let _ = write!(f, "e_shoff: 0x{:02x}; ", self.e_shoff);
+
+        let _ = write!(f, "e_flags: 0x{:02x}; ", self.e_flags);
+
+        let _ = write!(f, "e_shsize: 0x{:02x}; ", self.e_shsize);
+
+        let _ = write!(f, "e_phentsize: 0x{:02x}; ", self.e_phentsize);
+
+        let _ = write!(f, "e_phnum: 0x{:02x}; ", self.e_phnum);
+
+        let _ = write!(f, "e_shentsize: 0x{:02x}; ", self.e_shentsize);
+
+        let _ = write!(f, "e_shnum: 0x{:02x}; ", self.e_shnum);
+
+        write!(f, "e_shstrndx: 0x{:02x}", self.e_shstrndx)
+    }
+}
+/* Implementation of format to show 64-bit header */
+
+impl fmt::Display for ELFHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ELFHeader::Elf32(h) => write!(f, "{}", h),
+            ELFHeader::Elf64(h) => write!(f, "{}", h),
+        }
+    }
+}
+/* Implementation of format to show a generic header */
@@ -17,9 +17,11 @@
 
 use std::fs::File;
 use std::fmt;
+use std::io;
 use std::io::Read;
 use std::io::Seek;
 use std::io::SeekFrom;
+use std::io::Write;
 
 const EI_CLASS_32 :u8 = 0x01;
 /* ELF ident 32-bit format */
@@ -86,6 +88,41 @@ const EI_OSABI_FENIX_OS :u8 = 0x10;
 const EI_OSABI_CLOUDABI :u8 = 0x11;
 /* ELF ident CloudABI OS */
 
+enum ElfError {
+    Io(io::Error),                         /* underlying I/O failure */
+    BadMagic,                              /* the ELF magic is missing */
+    UnsupportedClass(u8),                  /* ei_class is neither 32 nor 64 */
+    UnsupportedData(u8),                   /* ei_data is neither endianness */
+    Truncated { expected :usize, got :usize }, /* short read */
+    UnknownOsAbi(u8)                       /* ei_osabi is not recognised */
+}
+/* Error reported by the ELF readers */
+
+impl From<io::Error> for ElfError {
+    fn from(error :io::Error) -> ElfError {
+        ElfError::Io(error)
+    }
+}
+/* Lets readers propagate io::Error through the ? operator */
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElfError::Io(e) => write!(f, "I/O error: {}", e),
+            ElfError::BadMagic => write!(f, "not an ELF file"),
+            ElfError::UnsupportedClass(c) =>
+                write!(f, "unsupported class: 0x{:02x}", c),
+            ElfError::UnsupportedData(d) =>
+                write!(f, "unsupported data encoding: 0x{:02x}", d),
+            ElfError::Truncated { expected, got } =>
+                write!(f, "truncated: expected {} bytes, got {}", expected, got),
+            ElfError::UnknownOsAbi(a) =>
+                write!(f, "unknown OS ABI: 0x{:02x}", a),
+        }
+    }
+}
+/* Implementation of format to show an error */
+
 struct ELFIdent {
     ei_mag0 :u8,              /* 0x00: magic number 0: 0x7f*/
     ei_mag1 :u8,              /* 0x01: magic number 1: 0x45 (E) */
@@ -116,12 +153,21 @@ fn parse_elf_ident(buf :&[u8; 16]) -> ELFIdent {
 }
 /* Parses a 16-byte buffer into am ELFIdent */
 
-fn check_elf_ident(ident :&ELFIdent) -> bool {
+fn validate_elf_ident(ident :&ELFIdent) -> Result<(), ElfError> {
     if ident.ei_mag0 != 0x7f || ident.ei_mag1 != 0x45 ||
-        ident.ei_mag2 != 0x4c || ident.ei_mag3 != 0x46 ||
-        (ident.ei_class != EI_CLASS_32 && ident.ei_class != EI_CLASS_64) ||
-        (ident.ei_data != EI_DATA_LITTLE && ident.ei_data != EI_DATA_BIG) ||
-        (ident.ei_osabi != EI_OSABI_SYSTEM_V &&
+        ident.ei_mag2 != 0x4c || ident.ei_mag3 != 0x46 {
+        return Err(ElfError::BadMagic);
+    }
+
+    if ident.ei_class != EI_CLASS_32 && ident.ei_class != EI_CLASS_64 {
+        return Err(ElfError::UnsupportedClass(ident.ei_class));
+    }
+
+    if ident.ei_data != EI_DATA_LITTLE && ident.ei_data != EI_DATA_BIG {
+        return Err(ElfError::UnsupportedData(ident.ei_data));
+    }
+
+    if ident.ei_osabi != EI_OSABI_SYSTEM_V &&
         ident.ei_osabi != EI_OSABI_HP_UX && ident.ei_osabi != EI_OSABI_NETBSD &&
         ident.ei_osabi != EI_OSABI_LINUX &&
         ident.ei_osabi != EI_OSABI_GNU_HURD &&
@@ -134,35 +180,89 @@ fn check_elf_ident(ident :&ELFIdent) -> bool {
         ident.ei_osabi != EI_OSABI_NONSTOP_KERNEL &&
         ident.ei_osabi != EI_OSABI_AROS &&
         ident.ei_osabi != EI_OSABI_FENIX_OS &&
-        ident.ei_osabi != EI_OSABI_CLOUDABI) {
-        return false;
+        ident.ei_osabi != EI_OSABI_CLOUDABI {
+        return Err(ElfError::UnknownOsAbi(ident.ei_osabi));
     }
 
-    true
+    Ok(())
+}
+/* Validates an ELFIdent reporting the first problem found */
+
+fn check_elf_ident(ident :&ELFIdent) -> bool {
+    validate_elf_ident(ident).is_ok()
 }
 /* Checks an ELFIdent */
 
-fn read_elf_ident(file :&mut File) -> Option<ELFIdent> {
+fn read_elf_ident(file :&mut File) -> Result<ELFIdent, ElfError> {
     let mut buf = [0u8; 16];
 
-    if let Err(_) = file.seek(SeekFrom::Start(0)) {
-        return None;
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut got = 0;
+    while got < buf.len() {
+        match file.read(&mut buf[got..])? {
+            0 => break,
+            n => got += n,
+        }
     }
-    
-    if let Err(_) = file.read_exact(&mut buf) {
-        return None;
+
+    if got < buf.len() {
+        return Err(ElfError::Truncated { expected: buf.len(), got });
     }
 
     let ident = parse_elf_ident(&buf);
 
-    if !check_elf_ident(&ident) {
-        return None;
-    }
+    validate_elf_ident(&ident)?;
 
-    Some(ident)
+    Ok(ident)
 }
 /* Reads the ELF ident from file */
 
+fn write_elf_ident(file :&mut File, ident :&ELFIdent) -> bool {
+    if !check_elf_ident(ident) {
+        return false;
+    }
+
+    let mut buf = [0u8; 16];
+    buf[0] = ident.ei_mag0;
+    buf[1] = ident.ei_mag1;
+    buf[2] = ident.ei_mag2;
+    buf[3] = ident.ei_mag3;
+    buf[4] = ident.ei_class;
+    buf[5] = ident.ei_data;
+    buf[6] = ident.ei_version;
+    buf[7] = ident.ei_osabi;
+    buf[8] = ident.ei_abiversion;
+
+    if let Err(_) = file.seek(SeekFrom::Start(0)) {
+        return false;
+    }
+
+    if let Err(_) = file.write_all(&buf) {
+        return false;
+    }
+
+    true
+}
+/* Writes an ELFIdent back to file */
+
+fn set_osabi(file :&mut File, osabi :u8, abiversion :u8) -> bool {
+    if let Err(_) = read_elf_ident(file) {
+        return false;
+    }
+
+    if let Err(_) = file.seek(SeekFrom::Start(0x07)) {
+        return false;
+    }
+
+    if let Err(_) = file.write_all(&[osabi, abiversion]) {
+        return false;
+    }
+
+    true
+}
+/* Rewrites the ei_osabi and ei_abiversion bytes in place */
+
 impl fmt::Display for ELFIdent {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let _ = write!(f, "ei_mag: 0x{0:02x} 0x{1:02x} 0x{2:02x} 0x{3:02x}; ",
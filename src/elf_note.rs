@@ -0,0 +1,162 @@
+/* elf_note.rs parses ELF note sections for OS/ABI branding
+ * Copyright (C) 2019  Bruno Mondelo Giaramita
+
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+const SHT_NOTE :u32 = 0x07;
+/* ELF section type note */
+
+const NT_GNU_ABI_TAG :u32 = 0x01;
+/* ELF note GNU ABI tag descriptor */
+
+const GNU_ABI_TAG_LINUX :u32 = 0x00;
+/* ELF note GNU ABI tag Linux OS */
+
+const GNU_ABI_TAG_GNU :u32 = 0x01;
+/* ELF note GNU ABI tag GNU OS */
+
+const GNU_ABI_TAG_SOLARIS2 :u32 = 0x02;
+/* ELF note GNU ABI tag Solaris 2 OS */
+
+const GNU_ABI_TAG_FREEBSD :u32 = 0x03;
+/* ELF note GNU ABI tag FreeBSD OS */
+
+struct ElfNote {
+    owner :String,            /* owner name (GNU, FreeBSD, NetBSD) */
+    note_type :u32,           /* type of the note */
+    os :String,               /* decoded OS for NT_GNU_ABI_TAG */
+    abi :(u32, u32, u32)      /* minimum kernel version (major, minor, patch) */
+}
+/* Structure to define an ELF note entry */
+
+fn gnu_abi_os(word :u32) -> String {
+    match word {
+        GNU_ABI_TAG_LINUX => String::from("Linux"),
+        GNU_ABI_TAG_GNU => String::from("GNU"),
+        GNU_ABI_TAG_SOLARIS2 => String::from("Solaris 2"),
+        GNU_ABI_TAG_FREEBSD => String::from("FreeBSD"),
+        _ => String::from("unknown"),
+    }
+}
+/* Decodes the OS word of a GNU ABI tag descriptor */
+
+fn parse_notes(buf :&[u8], ei_data :u8) -> Vec<ElfNote> {
+    let mut notes :Vec<ElfNote> = Vec::new();
+    let mut off :usize = 0;
+
+    while off + 12 <= buf.len() {
+        let (namesz, descsz, note_type) = if ei_data == EI_DATA_BIG {
+            (BigEndian::read_u32(&buf[off..off + 4]),
+             BigEndian::read_u32(&buf[off + 4..off + 8]),
+             BigEndian::read_u32(&buf[off + 8..off + 12]))
+        }
+        else {
+            (LittleEndian::read_u32(&buf[off..off + 4]),
+             LittleEndian::read_u32(&buf[off + 4..off + 8]),
+             LittleEndian::read_u32(&buf[off + 8..off + 12]))
+        };
+
+        let name_off = off + 12;
+        let desc_off = name_off + (((namesz + 3) & !3) as usize);
+        let desc_end = desc_off + (descsz as usize);
+
+        if desc_end > buf.len() {
+            break;
+        }
+
+        let mut owner = String::new();
+        if namesz > 0 {
+            let raw = &buf[name_off..name_off + (namesz as usize)];
+            let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+            owner = String::from_utf8_lossy(&raw[..end]).into_owned();
+        }
+
+        let mut os = String::new();
+        let mut abi = (0u32, 0u32, 0u32);
+
+        if note_type == NT_GNU_ABI_TAG && descsz >= 16 {
+            let d = &buf[desc_off..desc_off + 16];
+            let (w0, w1, w2, w3) = if ei_data == EI_DATA_BIG {
+                (BigEndian::read_u32(&d[0..4]),
+                 BigEndian::read_u32(&d[4..8]),
+                 BigEndian::read_u32(&d[8..12]),
+                 BigEndian::read_u32(&d[12..16]))
+            }
+            else {
+                (LittleEndian::read_u32(&d[0..4]),
+                 LittleEndian::read_u32(&d[4..8]),
+                 LittleEndian::read_u32(&d[8..12]),
+                 LittleEndian::read_u32(&d[12..16]))
+            };
+            os = gnu_abi_os(w0);
+            abi = (w1, w2, w3);
+        }
+
+        notes.push(ElfNote {
+            owner: owner,
+            note_type: note_type,
+            os: os,
+            abi: abi
+        });
+
+        off = desc_end + ((4 - (descsz as usize & 3)) & 3);
+    }
+
+    notes
+}
+/* Parses a note section body into a list of notes */
+
+fn read_notes(file :&mut File, header :&ELFHeader, sections :&[SectionHeader])
+    -> Vec<ElfNote> {
+    let ei_data = match header {
+        ELFHeader::Elf32(h) => h.e_ident.ei_data,
+        ELFHeader::Elf64(h) => h.e_ident.ei_data,
+    };
+
+    let mut notes :Vec<ElfNote> = Vec::new();
+
+    for section in sections.iter() {
+        if section.sh_type != SHT_NOTE {
+            continue;
+        }
+
+        let mut buf = vec![0u8; section.sh_size as usize];
+
+        if file.seek(SeekFrom::Start(section.sh_offset)).is_ok() &&
+            file.read_exact(&mut buf).is_ok() {
+            notes.append(&mut parse_notes(&buf, ei_data));
+        }
+    }
+
+    notes
+}
+/* Reads every note section from file and decodes its entries */
+
+impl fmt::Display for ElfNote {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let _ = write!(f, "owner: {}; ", self.owner);
+
+        let _ = write!(f, "type: 0x{:02x}; ", self.note_type);
+
+        if self.note_type == NT_GNU_ABI_TAG {
+            let _ = write!(f, "os: {}; ", self.os);
+            return write!(f, "abi: {}.{}.{}", self.abi.0, self.abi.1,
+                self.abi.2);
+        }
+
+        write!(f, "os: -")
+    }
+}
+/* Implementation of format to show a note */
@@ -0,0 +1,175 @@
+/* section_header.rs defines the ELF section header table
+ * Copyright (C) 2019  Bruno Mondelo Giaramita
+
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+struct SectionHeader {
+    sh_name :u32,             /* offset of the name into the .shstrtab */
+    sh_type :u32,             /* type of the section */
+    sh_flags :u64,            /* attributes of the section */
+    sh_addr :u64,             /* virtual address in memory */
+    sh_offset :u64,           /* offset of the section in the file */
+    sh_size :u64,             /* size in bytes of the section */
+    sh_link :u32,             /* index of an associated section */
+    sh_info :u32,             /* extra information of the section */
+    sh_addralign :u64,        /* required alignment of the section */
+    sh_entsize :u64,          /* size of an entry for tabular sections */
+    name :String              /* resolved name from the .shstrtab */
+}
+/* Structure to define a section header table entry */
+
+fn parse_section_header(buf :&[u8], ei_data :u8, ei_class :u8) -> SectionHeader {
+    let mut sh_name :u32 = Default::default();
+    let mut sh_type :u32 = Default::default();
+    let mut sh_flags :u64 = Default::default();
+    let mut sh_addr :u64 = Default::default();
+    let mut sh_offset :u64 = Default::default();
+    let mut sh_size :u64 = Default::default();
+    let mut sh_link :u32 = Default::default();
+    let mut sh_info :u32 = Default::default();
+    let mut sh_addralign :u64 = Default::default();
+    let mut sh_entsize :u64 = Default::default();
+
+    if ei_class == EI_CLASS_64 {
+        if ei_data == EI_DATA_LITTLE {
+            sh_name = LittleEndian::read_u32(&buf[0x00..0x04]);
+            sh_type = LittleEndian::read_u32(&buf[0x04..0x08]);
+            sh_flags = LittleEndian::read_u64(&buf[0x08..0x10]);
+            sh_addr = LittleEndian::read_u64(&buf[0x10..0x18]);
+            sh_offset = LittleEndian::read_u64(&buf[0x18..0x20]);
+            sh_size = LittleEndian::read_u64(&buf[0x20..0x28]);
+            sh_link = LittleEndian::read_u32(&buf[0x28..0x2c]);
+            sh_info = LittleEndian::read_u32(&buf[0x2c..0x30]);
+            sh_addralign = LittleEndian::read_u64(&buf[0x30..0x38]);
+            sh_entsize = LittleEndian::read_u64(&buf[0x38..0x40]);
+        }
+        else if ei_data == EI_DATA_BIG {
+            sh_name = BigEndian::read_u32(&buf[0x00..0x04]);
+            sh_type = BigEndian::read_u32(&buf[0x04..0x08]);
+            sh_flags = BigEndian::read_u64(&buf[0x08..0x10]);
+            sh_addr = BigEndian::read_u64(&buf[0x10..0x18]);
+            sh_offset = BigEndian::read_u64(&buf[0x18..0x20]);
+            sh_size = BigEndian::read_u64(&buf[0x20..0x28]);
+            sh_link = BigEndian::read_u32(&buf[0x28..0x2c]);
+            sh_info = BigEndian::read_u32(&buf[0x2c..0x30]);
+            sh_addralign = BigEndian::read_u64(&buf[0x30..0x38]);
+            sh_entsize = BigEndian::read_u64(&buf[0x38..0x40]);
+        }
+    }
+    else {
+        if ei_data == EI_DATA_LITTLE {
+            sh_name = LittleEndian::read_u32(&buf[0x00..0x04]);
+            sh_type = LittleEndian::read_u32(&buf[0x04..0x08]);
+            sh_flags = LittleEndian::read_u32(&buf[0x08..0x0c]) as u64;
+            sh_addr = LittleEndian::read_u32(&buf[0x0c..0x10]) as u64;
+            sh_offset = LittleEndian::read_u32(&buf[0x10..0x14]) as u64;
+            sh_size = LittleEndian::read_u32(&buf[0x14..0x18]) as u64;
+            sh_link = LittleEndian::read_u32(&buf[0x18..0x1c]);
+            sh_info = LittleEndian::read_u32(&buf[0x1c..0x20]);
+            sh_addralign = LittleEndian::read_u32(&buf[0x20..0x24]) as u64;
+            sh_entsize = LittleEndian::read_u32(&buf[0x24..0x28]) as u64;
+        }
+        else if ei_data == EI_DATA_BIG {
+            sh_name = BigEndian::read_u32(&buf[0x00..0x04]);
+            sh_type = BigEndian::read_u32(&buf[0x04..0x08]);
+            sh_flags = BigEndian::read_u32(&buf[0x08..0x0c]) as u64;
+            sh_addr = BigEndian::read_u32(&buf[0x0c..0x10]) as u64;
+            sh_offset = BigEndian::read_u32(&buf[0x10..0x14]) as u64;
+            sh_size = BigEndian::read_u32(&buf[0x14..0x18]) as u64;
+            sh_link = BigEndian::read_u32(&buf[0x18..0x1c]);
+            sh_info = BigEndian::read_u32(&buf[0x1c..0x20]);
+            sh_addralign = BigEndian::read_u32(&buf[0x20..0x24]) as u64;
+            sh_entsize = BigEndian::read_u32(&buf[0x24..0x28]) as u64;
+        }
+    }
+
+    SectionHeader {
+        sh_name: sh_name,
+        sh_type: sh_type,
+        sh_flags: sh_flags,
+        sh_addr: sh_addr,
+        sh_offset: sh_offset,
+        sh_size: sh_size,
+        sh_link: sh_link,
+        sh_info: sh_info,
+        sh_addralign: sh_addralign,
+        sh_entsize: sh_entsize,
+        name: String::new()
+    }
+}
+/* Parses a section header table entry from a buffer */
+
+fn resolve_section_name(strtab :&[u8], offset :u32) -> String {
+    let start = offset as usize;
+
+    if start >= strtab.len() {
+        return String::new();
+    }
+
+    let end = strtab[start..].iter()
+        .position(|&b| b == 0)
+        .map(|p| start + p)
+        .unwrap_or(strtab.len());
+
+    String::from_utf8_lossy(&strtab[start..end]).into_owned()
+}
+/* Resolves a sh_name offset into a name from the section string table */
+
+fn read_section_headers(file :&mut File, header :&ELFHeader) -> Vec<SectionHeader> {
+    let (ei_data, ei_class, e_shoff, e_shnum, e_shentsize, e_shstrndx) = match header {
+        ELFHeader::Elf32(h) => (h.e_ident.ei_data, h.e_ident.ei_class,
+            h.e_shoff as u64, h.e_shnum, h.e_shentsize, h.e_shstrndx),
+        ELFHeader::Elf64(h) => (h.e_ident.ei_data, h.e_ident.ei_class,
+            h.e_shoff, h.e_shnum, h.e_shentsize, h.e_shstrndx),
+    };
+
+    let mut sections :Vec<SectionHeader> = Vec::new();
+
+    if e_shoff == 0 || e_shnum == 0 {
+        return sections;
+    }
+
+    for i in 0..e_shnum {
+        let mut buf = vec![0u8; e_shentsize as usize];
+
+        if let Err(_) = file.seek(SeekFrom::Start(e_shoff +
+            (i as u64) * (e_shentsize as u64))) {
+            return sections;
+        }
+
+        if let Err(_) = file.read_exact(&mut buf) {
+            return sections;
+        }
+
+        sections.push(parse_section_header(&buf, ei_data, ei_class));
+    }
+
+    /* Load the section header string table and resolve every name */
+    if (e_shstrndx as usize) < sections.len() {
+        let strtab_off = sections[e_shstrndx as usize].sh_offset;
+        let strtab_size = sections[e_shstrndx as usize].sh_size;
+        let mut strtab = vec![0u8; strtab_size as usize];
+
+        if file.seek(SeekFrom::Start(strtab_off)).is_ok() &&
+            file.read_exact(&mut strtab).is_ok() {
+            for section in sections.iter_mut() {
+                section.name = resolve_section_name(&strtab, section.sh_name);
+            }
+        }
+    }
+
+    sections
+}
+/* Reads the section header table from file resolving names */